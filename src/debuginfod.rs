@@ -0,0 +1,115 @@
+//! Optional debuginfod client. When no local split-debug file exists, fetch
+//! one by build-id from a debuginfod server, the same way gdb/elfutils
+//! already do, and cache the result locally keyed by build-id.
+
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use binaryninja::settings::Settings;
+
+use log::{info, warn};
+
+const SERVERS_SETTING: &str = "loadSymbols.debuginfodUrls";
+const CACHE_SETTING: &str = "loadSymbols.debuginfodCache";
+
+pub fn register_settings() {
+    let settings = Settings::new("default");
+    settings.register_setting_json(
+        SERVERS_SETTING,
+        r#"{
+            "title": "Debuginfod servers",
+            "type": "string",
+            "default": "",
+            "description": "Space-separated debuginfod server URLs queried for debug files that aren't installed locally. Falls back to the DEBUGINFOD_URLS environment variable when left empty."
+        }"#,
+    );
+    settings.register_setting_json(
+        CACHE_SETTING,
+        r#"{
+            "title": "Debuginfod cache directory",
+            "type": "string",
+            "default": "",
+            "description": "Directory used to cache debug files fetched from a debuginfod server, keyed by build-id. Defaults to $XDG_CACHE_HOME/debuginfod_client, or ~/.cache/debuginfod_client."
+        }"#,
+    );
+}
+
+fn servers() -> Vec<String> {
+    let settings = Settings::new("default");
+    let configured = settings.get_string(SERVERS_SETTING, None, None).to_string();
+    let urls = if configured.trim().is_empty() {
+        std::env::var("DEBUGINFOD_URLS").unwrap_or_default()
+    } else {
+        configured
+    };
+    urls.split_whitespace().map(str::to_string).collect()
+}
+
+fn cache_dir() -> PathBuf {
+    let settings = Settings::new("default");
+    let configured = settings.get_string(CACHE_SETTING, None, None);
+    if !configured.is_empty() {
+        return PathBuf::from(configured);
+    }
+    if let Ok(xdg) = std::env::var("XDG_CACHE_HOME") {
+        return Path::new(&xdg).join("debuginfod_client");
+    }
+    let home = std::env::var("HOME").unwrap_or_else(|_| "/".to_string());
+    Path::new(&home).join(".cache").join("debuginfod_client")
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn cache_path(build_id: &[u8]) -> PathBuf {
+    cache_dir().join(to_hex(build_id)).join("debuginfo")
+}
+
+/// Returns the locally cached debug file for `build_id`, if one was already
+/// fetched. Does no network I/O, so this is safe to call from a cheap,
+/// synchronous probe like `is_valid`.
+pub fn cached(build_id: &[u8]) -> Option<PathBuf> {
+    let path = cache_path(build_id);
+    path.exists().then_some(path)
+}
+
+/// Fetches the debug file for `build_id`, checking the local cache first.
+/// Returns `None` if no configured server has it. Issues a blocking HTTP
+/// request per configured server on a cache miss, so only call this once
+/// the user has actually committed to importing debug info.
+pub fn fetch(build_id: &[u8]) -> Option<PathBuf> {
+    let id = to_hex(build_id);
+    let cached = cache_path(build_id);
+    if cached.exists() {
+        return Some(cached);
+    }
+
+    for server in servers() {
+        let url = format!("{}/buildid/{}/debuginfo", server.trim_end_matches('/'), id);
+        let response = match ureq::get(&url).call() {
+            Ok(response) => response,
+            Err(err) => {
+                warn!("debuginfod request to {} failed: {}", url, err);
+                continue;
+            }
+        };
+
+        let mut body = Vec::new();
+        if response.into_reader().read_to_end(&mut body).is_err() {
+            continue;
+        }
+        if let Some(dir) = cached.parent() {
+            if fs::create_dir_all(dir).is_err() {
+                continue;
+            }
+        }
+        if fs::write(&cached, &body).is_ok() {
+            info!("Fetched debug file for build-id {} from {}", id, server);
+            return Some(cached);
+        }
+    }
+
+    None
+}
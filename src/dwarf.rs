@@ -0,0 +1,424 @@
+//! DWARF-backed type recovery. When the located debug file carries
+//! `.debug_info`/`.debug_abbrev`/`.debug_line`, this walks the DIE tree with
+//! `gimli` to recover real variable/struct/union/enum/typedef and function
+//! signature types, registering them on the `DebugInfo` instead of the
+//! symbol table's bare `Type::void()`.
+//!
+//! The symbol-table path in `lib.rs` stays as a fallback for binaries that
+//! ship a `.dynsym` but no DWARF (e.g. stripped-but-dynamic binaries).
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::error::Error;
+
+use binaryninja::debuginfo::DebugInfo;
+use binaryninja::platform::Platform;
+use binaryninja::rc::Ref;
+use binaryninja::types::{EnumerationBuilder, FunctionParameter, StructureBuilder, Type};
+
+use gimli::{AttributeValue, DebuggingInformationEntry, Dwarf, EndianSlice, RunTimeEndian, Unit};
+
+use object::{Object, ObjectSection};
+
+type R<'a> = EndianSlice<'a, RunTimeEndian>;
+type Die<'a, 'b> = DebuggingInformationEntry<'a, 'b, R<'a>>;
+
+/// Every (non-split, non-dwo) DWARF section `gimli::Dwarf::load` may ask for.
+const DWARF_SECTION_IDS: &[gimli::SectionId] = &[
+    gimli::SectionId::DebugAbbrev,
+    gimli::SectionId::DebugAddr,
+    gimli::SectionId::DebugAranges,
+    gimli::SectionId::DebugInfo,
+    gimli::SectionId::DebugLine,
+    gimli::SectionId::DebugLineStr,
+    gimli::SectionId::DebugLoc,
+    gimli::SectionId::DebugLocLists,
+    gimli::SectionId::DebugRanges,
+    gimli::SectionId::DebugRngLists,
+    gimli::SectionId::DebugStr,
+    gimli::SectionId::DebugStrOffsets,
+    gimli::SectionId::DebugTypes,
+];
+
+/// True when the object carries enough DWARF sections to be worth walking.
+pub fn has_dwarf_info(obj: &object::File) -> bool {
+    [
+        gimli::SectionId::DebugInfo,
+        gimli::SectionId::DebugAbbrev,
+        gimli::SectionId::DebugLine,
+    ]
+    .iter()
+    .all(|id| obj.section_by_name(id.name()).is_some())
+}
+
+fn endian_of(obj: &object::File) -> RunTimeEndian {
+    if obj.is_little_endian() {
+        RunTimeEndian::Little
+    } else {
+        RunTimeEndian::Big
+    }
+}
+
+/// Walks every compilation unit's DIE tree, registering types, data
+/// variables and function signatures on `debug_info`. `platform` is stamped
+/// onto every recovered function, having already been validated against the
+/// view's architecture by the caller. `progress` is polled every
+/// [`super::PROGRESS_BATCH`] DIEs and, if it returns `Err`, the walk stops
+/// early instead of continuing to completion - in which case this returns
+/// `Ok(false)` rather than `Ok(true)`, since a cancelled import is not the
+/// same outcome as one that finished.
+pub fn parse_dwarf(
+    debug_info: &mut DebugInfo,
+    obj: &object::File,
+    platform: &Ref<Platform>,
+    progress: &dyn Fn(usize, usize) -> Result<(), ()>,
+) -> Result<bool, Box<dyn Error>> {
+    let endian = endian_of(obj);
+
+    // Pre-fetch every section's data into a local table up front. For the
+    // common uncompressed case `uncompressed_data()` already returns a
+    // `Cow::Borrowed` slice into `obj`'s mmap, so this is effectively free;
+    // only compressed sections get decompressed into an owned buffer. Either
+    // way the data lives exactly as long as this function call, owned by
+    // `sections` below, instead of being `Box::leak`'d for the life of the
+    // process on every binary parsed.
+    let sections: HashMap<gimli::SectionId, Cow<[u8]>> = DWARF_SECTION_IDS
+        .iter()
+        .map(|&id| {
+            let data = obj
+                .section_by_name(id.name())
+                .and_then(|s| s.uncompressed_data().ok())
+                .unwrap_or(Cow::Borrowed(&[] as &[u8]));
+            (id, data)
+        })
+        .collect();
+
+    let load_section = |id: gimli::SectionId| -> Result<R<'_>, gimli::Error> {
+        let data = sections.get(&id).map(Cow::as_ref).unwrap_or(&[]);
+        Ok(EndianSlice::new(data, endian))
+    };
+    let dwarf = Dwarf::load(load_section)?;
+
+    let total = count_entries(&dwarf)?;
+    let mut processed = 0usize;
+    let mut cancelled = false;
+
+    let mut units = dwarf.units();
+    'units: while let Some(header) = units.next()? {
+        let unit = dwarf.unit(header)?;
+        // `entry.offset()`/`DW_AT_type`'s `UnitOffset` is relative to the
+        // start of *this* unit, not a global `.debug_info` offset, so the
+        // type cache can't be shared across units - two different CUs can
+        // easily have unrelated DIEs at the same unit-relative offset.
+        let mut cache: HashMap<usize, Ref<Type>> = HashMap::new();
+        let mut cursor = unit.entries();
+        while let Some((_, entry)) = cursor.next_dfs()? {
+            processed += 1;
+            if processed % super::PROGRESS_BATCH == 0 && progress(processed, total).is_err() {
+                cancelled = true;
+                break 'units;
+            }
+
+            match entry.tag() {
+                gimli::DW_TAG_variable => {
+                    register_variable(debug_info, &dwarf, &unit, entry, &mut cache)?;
+                }
+                gimli::DW_TAG_subprogram => {
+                    register_subprogram(debug_info, &dwarf, &unit, entry, &mut cache, platform)?;
+                }
+                gimli::DW_TAG_structure_type
+                | gimli::DW_TAG_union_type
+                | gimli::DW_TAG_enumeration_type
+                | gimli::DW_TAG_typedef => {
+                    if let Some(name) = die_name(&dwarf, &unit, entry)? {
+                        if let Some(ty) = resolve_inline(&dwarf, &unit, entry, &mut cache)? {
+                            debug_info.add_type(name, &ty);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    if cancelled {
+        return Ok(false);
+    }
+    let _ = progress(total, total);
+
+    Ok(true)
+}
+
+/// A first, cheap pass over every unit just to get a total DIE count for
+/// progress reporting before the real (type-building) pass begins.
+fn count_entries(dwarf: &Dwarf<R<'_>>) -> Result<usize, Box<dyn Error>> {
+    let mut total = 0;
+    let mut units = dwarf.units();
+    while let Some(header) = units.next()? {
+        let unit = dwarf.unit(header)?;
+        let mut cursor = unit.entries();
+        while cursor.next_dfs()?.is_some() {
+            total += 1;
+        }
+    }
+    Ok(total)
+}
+
+fn die_name(
+    dwarf: &Dwarf<R<'_>>,
+    unit: &Unit<R<'_>>,
+    entry: &Die<'_, '_>,
+) -> Result<Option<String>, Box<dyn Error>> {
+    match entry.attr_value(gimli::DW_AT_name)? {
+        Some(value) => Ok(Some(
+            dwarf
+                .attr_string(unit, value)?
+                .to_string_lossy()
+                .into_owned(),
+        )),
+        None => Ok(None),
+    }
+}
+
+/// Follows `DW_AT_type` to the referenced DIE and resolves it to a `Type`,
+/// memoizing by DIE offset so shared types (e.g. a struct used by many
+/// variables) are only built once.
+fn type_of(
+    dwarf: &Dwarf<R<'_>>,
+    unit: &Unit<R<'_>>,
+    entry: &Die<'_, '_>,
+    cache: &mut HashMap<usize, Ref<Type>>,
+) -> Result<Option<Ref<Type>>, Box<dyn Error>> {
+    let offset = match entry.attr_value(gimli::DW_AT_type)? {
+        Some(AttributeValue::UnitRef(offset)) => offset,
+        _ => return Ok(None),
+    };
+
+    if let Some(cached) = cache.get(&offset.0) {
+        return Ok(Some(cached.clone()));
+    }
+
+    let referenced = unit.entry(offset)?;
+    let ty = resolve_inline(dwarf, unit, &referenced, cache)?;
+    if let Some(ty) = &ty {
+        cache.insert(offset.0, ty.clone());
+    }
+    Ok(ty)
+}
+
+/// Resolves `entry` itself (as opposed to following its `DW_AT_type`) into a
+/// `Type`, dispatching on tag.
+fn resolve_inline(
+    dwarf: &Dwarf<R<'_>>,
+    unit: &Unit<R<'_>>,
+    entry: &Die<'_, '_>,
+    cache: &mut HashMap<usize, Ref<Type>>,
+) -> Result<Option<Ref<Type>>, Box<dyn Error>> {
+    let ty = match entry.tag() {
+        gimli::DW_TAG_base_type => base_type(dwarf, unit, entry)?,
+        gimli::DW_TAG_pointer_type => {
+            let inner = type_of(dwarf, unit, entry, cache)?.unwrap_or_else(Type::void);
+            Some(Type::pointer(&inner))
+        }
+        gimli::DW_TAG_const_type | gimli::DW_TAG_volatile_type | gimli::DW_TAG_typedef => {
+            Some(type_of(dwarf, unit, entry, cache)?.unwrap_or_else(Type::void))
+        }
+        gimli::DW_TAG_structure_type | gimli::DW_TAG_union_type => {
+            structure_type(dwarf, unit, entry, cache)?
+        }
+        gimli::DW_TAG_enumeration_type => enumeration_type(dwarf, unit, entry)?,
+        _ => None,
+    };
+    Ok(ty)
+}
+
+fn base_type(
+    dwarf: &Dwarf<R<'_>>,
+    unit: &Unit<R<'_>>,
+    entry: &Die<'_, '_>,
+) -> Result<Option<Ref<Type>>, Box<dyn Error>> {
+    let size = match entry.attr_value(gimli::DW_AT_byte_size)? {
+        Some(AttributeValue::Udata(size)) => size as usize,
+        _ => return Ok(None),
+    };
+    let encoding = match entry.attr_value(gimli::DW_AT_encoding)? {
+        Some(AttributeValue::Encoding(encoding)) => encoding,
+        _ => return Ok(None),
+    };
+
+    let _ = dwarf;
+    Ok(Some(match encoding {
+        gimli::DW_ATE_boolean => Type::bool(),
+        gimli::DW_ATE_float => Type::float(size),
+        gimli::DW_ATE_signed | gimli::DW_ATE_signed_char => Type::int(size, true),
+        gimli::DW_ATE_unsigned | gimli::DW_ATE_unsigned_char => Type::int(size, false),
+        _ => Type::int(size, true),
+    }))
+}
+
+fn structure_type(
+    dwarf: &Dwarf<R<'_>>,
+    unit: &Unit<R<'_>>,
+    entry: &Die<'_, '_>,
+    cache: &mut HashMap<usize, Ref<Type>>,
+) -> Result<Option<Ref<Type>>, Box<dyn Error>> {
+    let mut builder = StructureBuilder::new();
+    for child in children(unit, entry)? {
+        if child.tag() != gimli::DW_TAG_member {
+            continue;
+        }
+        let Some(name) = die_name(dwarf, unit, &child)? else {
+            continue;
+        };
+        let Some(member_ty) = type_of(dwarf, unit, &child, cache)? else {
+            continue;
+        };
+        let offset = match child.attr_value(gimli::DW_AT_data_member_location)? {
+            Some(AttributeValue::Udata(offset)) => offset,
+            _ => 0,
+        };
+        builder.insert(&member_ty, name, offset, false, Default::default());
+    }
+    Ok(Some(Type::structure(&builder.finalize())))
+}
+
+fn enumeration_type(
+    dwarf: &Dwarf<R<'_>>,
+    unit: &Unit<R<'_>>,
+    entry: &Die<'_, '_>,
+) -> Result<Option<Ref<Type>>, Box<dyn Error>> {
+    let size = match entry.attr_value(gimli::DW_AT_byte_size)? {
+        Some(AttributeValue::Udata(size)) => size as usize,
+        _ => 4,
+    };
+    let mut builder = EnumerationBuilder::new();
+    for child in children(unit, entry)? {
+        if child.tag() != gimli::DW_TAG_enumerator {
+            continue;
+        }
+        let Some(name) = die_name(dwarf, unit, &child)? else {
+            continue;
+        };
+        let value = match child.attr_value(gimli::DW_AT_const_value)? {
+            Some(AttributeValue::Sdata(value)) => value,
+            Some(AttributeValue::Udata(value)) => value as i64,
+            _ => 0,
+        };
+        builder.insert(name, value);
+    }
+    Ok(Some(Type::enumeration(&builder.finalize(), size, false)))
+}
+
+fn register_variable(
+    debug_info: &mut DebugInfo,
+    dwarf: &Dwarf<R<'_>>,
+    unit: &Unit<R<'_>>,
+    entry: &Die<'_, '_>,
+    cache: &mut HashMap<usize, Ref<Type>>,
+) -> Result<(), Box<dyn Error>> {
+    let Some(name) = die_name(dwarf, unit, entry)? else {
+        return Ok(());
+    };
+    let Some(address) = variable_address(entry)? else {
+        return Ok(());
+    };
+    let ty = type_of(dwarf, unit, entry, cache)?.unwrap_or_else(Type::void);
+
+    debug_info.add_data_variable(address, &ty, Some(name));
+    Ok(())
+}
+
+fn variable_address(entry: &Die<'_, '_>) -> Result<Option<u64>, Box<dyn Error>> {
+    match entry.attr_value(gimli::DW_AT_location)? {
+        Some(AttributeValue::Exprloc(expr)) => {
+            let mut ops = expr.0;
+            match ops.read_u8() {
+                // DW_OP_addr: a single absolute address operand.
+                Ok(0x03) => Ok(ops.read_u64().ok()),
+                _ => Ok(None),
+            }
+        }
+        _ => Ok(None),
+    }
+}
+
+fn register_subprogram(
+    debug_info: &mut DebugInfo,
+    dwarf: &Dwarf<R<'_>>,
+    unit: &Unit<R<'_>>,
+    entry: &Die<'_, '_>,
+    cache: &mut HashMap<usize, Ref<Type>>,
+    platform: &Ref<Platform>,
+) -> Result<(), Box<dyn Error>> {
+    let Some(name) = die_name(dwarf, unit, entry)? else {
+        return Ok(());
+    };
+    let Some(AttributeValue::Addr(address)) = entry.attr_value(gimli::DW_AT_low_pc)? else {
+        return Ok(());
+    };
+
+    let return_type = type_of(dwarf, unit, entry, cache)?.unwrap_or_else(Type::void);
+
+    // `parameters` becomes `None` the moment any parameter's type can't be
+    // mapped, so a function that actually takes N arguments never ends up
+    // typed as taking zero - we'd rather import it with no type at all
+    // (name-only) than assert a wrong signature that misleads calling
+    // convention analysis.
+    let mut parameters = Some(Vec::new());
+    for child in children(unit, entry)? {
+        if child.tag() != gimli::DW_TAG_formal_parameter {
+            continue;
+        }
+        let Some(params) = parameters.as_mut() else {
+            break;
+        };
+        match type_of(dwarf, unit, &child, cache)? {
+            Some(param_ty) => {
+                let param_name = die_name(dwarf, unit, &child)?.unwrap_or_default();
+                params.push(FunctionParameter::new(param_ty, param_name));
+            }
+            None => parameters = None,
+        }
+    }
+
+    let function_type = parameters.map(|parameters| Type::function(&return_type, &parameters, false));
+
+    debug_info.add_function(
+        binaryninja::debuginfo::DebugFunctionInfo::new(
+            Some(name.clone()),
+            Some(name.clone()),
+            Some(name),
+            function_type,
+            Some(address),
+            Some(platform.clone()),
+        ),
+    );
+    Ok(())
+}
+
+/// Direct children of `parent` (depth + 1), found by re-walking from the
+/// unit root and tracking DFS depth relative to `parent`'s offset.
+fn children<'a, 'b>(
+    unit: &'b Unit<R<'a>>,
+    parent: &Die<'a, '_>,
+) -> Result<Vec<Die<'a, 'b>>, Box<dyn Error>> {
+    let parent_offset = parent.offset();
+    let mut cursor = unit.entries();
+    let mut found_parent = false;
+    let mut depth = 0i64;
+    let mut out = Vec::new();
+    while let Some((delta, entry)) = cursor.next_dfs()? {
+        depth += delta;
+        if found_parent {
+            if depth <= 0 {
+                break;
+            }
+            if depth == 1 {
+                out.push(entry.clone());
+            }
+        } else if entry.offset() == parent_offset {
+            found_parent = true;
+            depth = 0;
+        }
+    }
+    Ok(out)
+}
@@ -10,10 +10,10 @@ use binaryninja::logger;
 use binaryninja::platform::Platform;
 use binaryninja::rc::Ref;
 use binaryninja::string::BnStrCompatible;
-use binaryninja::types::Type;
+use binaryninja::types::{FunctionParameter, Type};
 
 use cpp_demangle::DemangleOptions;
-use object::{Object, ObjectSymbol, SymbolKind};
+use object::{Object, ObjectSection, ObjectSymbol, SymbolKind};
 
 use derivative::Derivative;
 
@@ -25,6 +25,11 @@ use std::path::{Path, PathBuf};
 
 use log::{error, info, warn, LevelFilter};
 
+mod debuginfod;
+mod dwarf;
+mod platform;
+mod signature;
+
 struct SymbolInfoParser;
 
 #[derive(Derivative)]
@@ -34,6 +39,7 @@ pub struct DebugFunctionInfoBuilder<S: BnStrCompatible> {
     full_name: Option<S>,
     raw_name: Option<S>,
     return_type: Option<Ref<Type>>,
+    parameters: Option<Vec<FunctionParameter<S>>>,
     address: Option<u64>,
     platform: Option<Ref<Platform>>,
 }
@@ -59,31 +65,94 @@ impl<S: BnStrCompatible> DebugFunctionInfoBuilder<S> {
         self
     }
 
+    pub fn return_type(mut self, return_type: Ref<Type>) -> Self {
+        self.return_type = Some(return_type);
+        self
+    }
+
+    pub fn parameters(mut self, parameters: Vec<FunctionParameter<S>>) -> Self {
+        self.parameters = Some(parameters);
+        self
+    }
+
     pub fn address(mut self, address: u64) -> Self {
         self.address = Some(address);
         self
     }
 
+    pub fn platform(mut self, platform: Ref<Platform>) -> Self {
+        self.platform = Some(platform);
+        self
+    }
+
     pub fn build(self) -> DebugFunctionInfo<S> {
+        // `parameters` only makes sense alongside a return type: together
+        // they form the function's `Type`, which is what the underlying API
+        // actually expects here despite the field being named `return_type`.
+        let function_type = match (self.return_type, self.parameters) {
+            (Some(return_type), Some(parameters)) => {
+                Some(Type::function(&return_type, &parameters, false))
+            }
+            (return_type, _) => return_type,
+        };
+
         DebugFunctionInfo::new(
             self.short_name,
             self.full_name,
             self.raw_name,
-            self.return_type,
+            function_type,
             self.address,
             self.platform,
         )
     }
 }
 
+// Rust mangles names as either the legacy `_ZN...17h<16 hex digits>E` scheme
+// (an Itanium-shaped prefix with a trailing hash component) or, from v0
+// onward, `_R` followed by its own nested-path grammar. `_R` alone is an
+// unambiguous signal, but a bare `_ZN`/`ZN` prefix is not: that's also the
+// nested-name production ordinary Itanium C++ symbols use, and
+// `rustc_demangle::try_demangle` tolerates trailing bytes it can't parse
+// rather than erroring, so it can "succeed" on plain C++ input. Requiring
+// the trailing hash segment rules those false positives out.
+fn is_rust_mangled(s: &str) -> bool {
+    s.starts_with("_R") || ((s.starts_with("_ZN") || s.starts_with("ZN")) && has_legacy_rust_hash(s))
+}
+
+fn has_legacy_rust_hash(s: &str) -> bool {
+    // "17h" + 16 hex digits + "E", right at the end of the name.
+    const HASH_LEN: usize = 3 + 16;
+    let Some(body) = s.strip_suffix('E') else {
+        return false;
+    };
+    body.len() >= HASH_LEN
+        && body[body.len() - HASH_LEN..].starts_with("17h")
+        && body[body.len() - 16..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
 fn demangle(s: &str) -> Result<String, Box<dyn Error>> {
-    let sym = cpp_demangle::Symbol::new(s)?;
-    let options = DemangleOptions::new().no_params().no_return_type();
-    let s = sym.demangle(&options)?;
-    Ok(s)
+    if is_rust_mangled(s) {
+        if let Ok(demangled) = rustc_demangle::try_demangle(s) {
+            return Ok(format!("{:#}", demangled));
+        }
+    }
+
+    if let Ok(sym) = cpp_demangle::Symbol::new(s) {
+        let options = DemangleOptions::new().no_params().no_return_type();
+        if let Ok(demangled) = sym.demangle(&options) {
+            return Ok(demangled);
+        }
+    }
+
+    let demangled = rustc_demangle::try_demangle(s)?;
+    Ok(format!("{:#}", demangled))
 }
 
-fn add_function(debug_info: &mut DebugInfo, symbol: &object::Symbol) -> Result<(), Box<dyn Error>> {
+fn add_function(
+    debug_info: &mut DebugInfo,
+    symbol: &object::Symbol,
+    platform: &Ref<Platform>,
+) -> Result<(), Box<dyn Error>> {
     let name = symbol.name()?;
     let demangled = match demangle(name) {
         Ok(d) => d,
@@ -92,12 +161,19 @@ fn add_function(debug_info: &mut DebugInfo, symbol: &object::Symbol) -> Result<(
 
     info!("Function added: {}: {:x?}", demangled, symbol.address());
 
-    let new_func: DebugFunctionInfo<&str> = DebugFunctionInfoBuilder::new()
+    let mut builder: DebugFunctionInfoBuilder<&str> = DebugFunctionInfoBuilder::new()
         .raw_name(name)
         .full_name(&demangled)
         .address(symbol.address())
-        .build();
-    debug_info.add_function(new_func);
+        .platform(platform.clone());
+
+    if let Some(signature) = signature::cpp_signature(name) {
+        builder = builder
+            .return_type(signature.return_type)
+            .parameters(signature.parameters);
+    }
+
+    debug_info.add_function(builder.build());
     Ok(())
 }
 
@@ -115,62 +191,194 @@ fn add_data(debug_info: &mut DebugInfo, symbol: &object::Symbol) -> Result<(), B
     Ok(())
 }
 
-fn get_symbols(debug_info: &mut DebugInfo, path: &PathBuf) -> Result<(), Box<dyn Error>> {
+// How many symbols/DIEs to process between progress callbacks. Calling back
+// on every single symbol makes the UI thread do far more work than the
+// import itself on a binary with hundreds of thousands of symbols.
+pub(crate) const PROGRESS_BATCH: usize = 256;
+
+/// Returns `Ok(true)` when the import ran to completion, `Ok(false)` when
+/// the progress callback cancelled it partway through - the two are not the
+/// same outcome, and callers must not report a cancelled import as success.
+fn get_symbols(
+    debug_info: &mut DebugInfo,
+    path: &PathBuf,
+    view: &BinaryView,
+    progress: &dyn Fn(usize, usize) -> Result<(), ()>,
+) -> Result<bool, Box<dyn Error>> {
     let file = fs::File::open(path)?;
     let file = unsafe { memmap2::Mmap::map(&file) }?;
     let file = object::File::parse(&*file)?;
-    file.symbols()
-        .filter(ObjectSymbol::is_definition)
-        .try_for_each(|symbol| match symbol.kind() {
-            SymbolKind::Text => add_function(debug_info, &symbol),
-            SymbolKind::Data => add_data(debug_info, &symbol),
-            _ => Ok(()),
-        })?;
 
-    Ok(())
+    let platform = platform::matching_platform(&file, view)
+        .ok_or("debug file architecture does not match the view; refusing to import")?;
+
+    if dwarf::has_dwarf_info(&file) {
+        info!("Parsing DWARF debug info from {}", path.to_string_lossy());
+        return dwarf::parse_dwarf(debug_info, &file, &platform, progress);
+    }
+
+    // No DWARF: fall back to the symbol table, which is all a
+    // stripped-but-dynsym binary has to offer.
+    let definitions: Vec<_> = file.symbols().filter(ObjectSymbol::is_definition).collect();
+    let total = definitions.len();
+
+    for (i, symbol) in definitions.iter().enumerate() {
+        match symbol.kind() {
+            SymbolKind::Text => add_function(debug_info, symbol, &platform)?,
+            SymbolKind::Data => add_data(debug_info, symbol)?,
+            _ => {}
+        }
+
+        if i % PROGRESS_BATCH == 0 && progress(i, total).is_err() {
+            warn!("Symbol import cancelled after {} of {} symbols", i, total);
+            return Ok(false);
+        }
+    }
+    let _ = progress(total, total);
+
+    Ok(true)
+}
+
+const BUILD_ID_DEBUG_DIR: &str = "/usr/lib/debug/.build-id";
+const DEBUG_ROOT: &str = "/usr/lib/debug";
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn with_object_file<T>(path: &Path, f: impl FnOnce(&object::File) -> Option<T>) -> Option<T> {
+    let file = fs::File::open(path).ok()?;
+    let mmap = unsafe { memmap2::Mmap::map(&file) }.ok()?;
+    let obj = object::File::parse(&*mmap).ok()?;
+    f(&obj)
 }
 
-fn get_debug_path(view: &BinaryView) -> Option<PathBuf> {
-    if let Ok(path) = fs::canonicalize(PathBuf::from(view.file().filename().to_string())) &&
-       let Ok(path) = path.strip_prefix("/") {
-        let f = Path::new("/usr/lib/debug")
-            .join(path);
-        let ext = match f.extension() {
-            Some(ext) => {
-                [ext.as_bytes(), b".debug"].concat()
-            },
-            None => b"debug".to_vec()
+fn build_id_of(bin_path: &Path) -> Option<Vec<u8>> {
+    with_object_file(bin_path, |obj| obj.build_id().ok().flatten().map(<[u8]>::to_vec))
+}
+
+// GDB/elfutils resolve split debug info by build-id first: `.note.gnu.build-id`
+// holds a SHA1-style id, the first byte of which becomes the `.build-id`
+// subdirectory and the rest the filename.
+fn build_id_debug_path(build_id: &[u8]) -> Option<PathBuf> {
+    if build_id.len() < 2 {
+        return None;
+    }
+    let (prefix, rest) = build_id.split_at(1);
+    let candidate = Path::new(BUILD_ID_DEBUG_DIR)
+        .join(to_hex(prefix))
+        .join(format!("{}.debug", to_hex(rest)));
+    candidate.exists().then_some(candidate)
+}
+
+// Falling back to `.gnu_debuglink`: a NUL-terminated filename, padded to a
+// 4-byte boundary, followed by the CRC32 of the debug file it points at.
+fn debuglink_of(bin_path: &Path) -> Option<(String, u32)> {
+    with_object_file(bin_path, |obj| {
+        let section = obj.section_by_name(".gnu_debuglink")?;
+        let data = section.data().ok()?;
+        let nul = data.iter().position(|&b| b == 0)?;
+        let name = std::str::from_utf8(&data[..nul]).ok()?.to_string();
+        let crc_offset = (nul + 1 + 3) & !3;
+        let crc = u32::from_le_bytes(data.get(crc_offset..crc_offset + 4)?.try_into().ok()?);
+        Some((name, crc))
+    })
+}
+
+fn crc32_of_file(path: &Path) -> Option<u32> {
+    let data = fs::read(path).ok()?;
+    let mut hasher = crc32fast::Hasher::new();
+    hasher.update(&data);
+    Some(hasher.finalize())
+}
+
+fn debuglink_debug_path(bin_path: &Path, name: &str, crc: u32) -> Option<PathBuf> {
+    let dir = bin_path.parent().unwrap_or_else(|| Path::new("/"));
+    let rooted = dir.strip_prefix("/").unwrap_or(dir);
+    [
+        dir.join(name),
+        dir.join(".debug").join(name),
+        Path::new(DEBUG_ROOT).join(rooted).join(name),
+    ]
+    .into_iter()
+    .find(|candidate| crc32_of_file(candidate) == Some(crc))
+}
+
+fn mirrored_debug_path(bin_path: &Path) -> Option<PathBuf> {
+    let path = bin_path.strip_prefix("/").ok()?;
+    let f = Path::new(DEBUG_ROOT).join(path);
+    let ext = match f.extension() {
+        Some(ext) => [ext.as_bytes(), b".debug"].concat(),
+        None => b"debug".to_vec(),
+    };
+    Some(f.with_extension(OsStr::from_bytes(ext.as_slice())))
+}
+
+// `is_valid` must stay a cheap, local-only probe: Binary Ninja can call it
+// for every registered parser on every opened view, regardless of whether
+// the user ever imports debug info, so it must never block on network I/O.
+// `allow_fetch` gates the one source that can: a debuginfod lookup falls
+// back to an already-cached hit under `is_valid`, and only reaches out to
+// the network once `parse_info` has committed to the import.
+fn get_debug_path(view: &BinaryView, allow_fetch: bool) -> Option<PathBuf> {
+    let bin_path = fs::canonicalize(PathBuf::from(view.file().filename().to_string())).ok()?;
+    let build_id = build_id_of(&bin_path);
+
+    if let Some(build_id) = &build_id {
+        if let Some(candidate) = build_id_debug_path(build_id) {
+            info!("Loading symbols via build-id from {}", candidate.to_string_lossy());
+            return Some(candidate);
+        }
+    }
+
+    if let Some((name, crc)) = debuglink_of(&bin_path) &&
+       let Some(candidate) = debuglink_debug_path(&bin_path, &name, crc) {
+        info!("Loading symbols via .gnu_debuglink from {}", candidate.to_string_lossy());
+        return Some(candidate);
+    }
+
+    // Only reach for debuginfod once every local resolution method (build-id
+    // dir, .gnu_debuglink) has come up empty.
+    if let Some(build_id) = &build_id {
+        let debuginfod_hit = if allow_fetch {
+            debuginfod::fetch(build_id)
+        } else {
+            debuginfod::cached(build_id)
         };
-        let debug_path = f.with_extension(OsStr::from_bytes(ext.as_slice()));
-        info!("Loading symbols from {}", debug_path.to_string_lossy());
-        Some(debug_path)
-    } else {
-        None
+        if let Some(candidate) = debuginfod_hit {
+            info!("Loading symbols fetched via debuginfod from {}", candidate.to_string_lossy());
+            return Some(candidate);
+        }
     }
+
+    let debug_path = mirrored_debug_path(&bin_path)?;
+    info!("Loading symbols from {}", debug_path.to_string_lossy());
+    Some(debug_path)
 }
 
 impl CustomDebugInfoParser for SymbolInfoParser {
     fn is_valid(&self, view: &BinaryView) -> bool {
-        warn!("Checking for {:?}", get_debug_path(view));
-        get_debug_path(view).is_some_and(|f| f.exists())
+        warn!("Checking for {:?}", get_debug_path(view, false));
+        get_debug_path(view, false).is_some_and(|f| f.exists())
     }
 
     fn parse_info(
         &self,
         debug_info: &mut DebugInfo,
         view: &BinaryView,
-        _progress: Box<dyn Fn(usize, usize) -> Result<(), ()>>,
+        progress: Box<dyn Fn(usize, usize) -> Result<(), ()>>,
     ) -> bool {
-        if let Some(debug_path) = get_debug_path(view) {
-            if let Err(err) = get_symbols(debug_info, &debug_path) {
-                error!("Loading symbols failed {:?}", err);
-                return false;
+        if let Some(debug_path) = get_debug_path(view, true) {
+            match get_symbols(debug_info, &debug_path, view, progress.as_ref()) {
+                Ok(completed) => return completed,
+                Err(err) => {
+                    error!("Loading symbols failed {:?}", err);
+                    return false;
+                }
             }
-        } else {
-            error!("Unable to load debug path");
-            return false;
         }
-        true
+        error!("Unable to load debug path");
+        false
     }
 }
 
@@ -178,6 +386,7 @@ impl CustomDebugInfoParser for SymbolInfoParser {
 pub extern "C" fn CorePluginInit() -> bool {
     logger::init(LevelFilter::Info).unwrap();
 
+    debuginfod::register_settings();
     DebugInfoParser::register("Symbol info parser", SymbolInfoParser {});
     true
 }
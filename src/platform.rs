@@ -0,0 +1,63 @@
+//! Validates that a located debug file's architecture matches the open
+//! `BinaryView` before anything from it gets imported, and resolves the
+//! `Platform` to stamp onto each imported function so Binary Ninja has the
+//! right calling convention.
+
+use binaryninja::binaryview::{BinaryView, BinaryViewExt};
+use binaryninja::platform::Platform;
+use binaryninja::rc::Ref;
+
+use object::Object;
+
+use log::error;
+
+// MIPS and 64-bit PowerPC are overwhelmingly little-endian in practice
+// (mipsel, ppc64le), and Binary Ninja names those architectures `*el`/`le`
+// rather than the big-endian default, so the endianness has to be folded
+// into the name or every mipsel/ppc64le binary fails to match its own view.
+fn object_arch_name(arch: object::Architecture, little_endian: bool) -> Option<&'static str> {
+    use object::Architecture::*;
+    Some(match arch {
+        X86_64 => "x86_64",
+        I386 => "x86",
+        Aarch64 => "aarch64",
+        Arm => "armv7",
+        Riscv32 => "riscv32",
+        Riscv64 => "riscv64",
+        Mips if little_endian => "mipsel32",
+        Mips => "mips32",
+        Mips64 if little_endian => "mips64el",
+        Mips64 => "mips64",
+        PowerPc => "ppc32",
+        PowerPc64 if little_endian => "ppc64le",
+        PowerPc64 => "ppc64",
+        _ => return None,
+    })
+}
+
+/// Returns the view's platform if the debug file's architecture matches it.
+/// On any mismatch (or an architecture we don't recognize) this logs a
+/// clear reason and returns `None`, so the caller can refuse the import
+/// outright instead of silently mislabeling symbols.
+pub fn matching_platform(obj: &object::File, view: &BinaryView) -> Option<Ref<Platform>> {
+    let platform = view.default_platform()?;
+    let view_arch = platform.arch().name().to_string();
+
+    let Some(debug_arch) = object_arch_name(obj.architecture(), obj.is_little_endian()) else {
+        error!(
+            "Debug file architecture {:?} is not recognized; refusing to import",
+            obj.architecture()
+        );
+        return None;
+    };
+
+    if debug_arch != view_arch {
+        error!(
+            "Debug file architecture ({}) does not match view architecture ({}); refusing to import",
+            debug_arch, view_arch
+        );
+        return None;
+    }
+
+    Some(platform)
+}
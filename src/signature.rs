@@ -0,0 +1,145 @@
+//! Translates a demangled C++ signature (return type and parameter list)
+//! into Binary Ninja `Type`s, so functions recovered from a symbol table
+//! carry real signatures instead of just a name.
+
+use binaryninja::rc::Ref;
+use binaryninja::types::{FunctionParameter, Type};
+
+use cpp_demangle::{DemangleOptions, Symbol};
+
+/// A C++ function signature recovered from a mangled name.
+pub struct Signature {
+    pub return_type: Ref<Type>,
+    pub parameters: Vec<FunctionParameter<&'static str>>,
+}
+
+/// Demangle `name` with parameters and a return type included, then
+/// translate the result into Binary Ninja types. Returns `None` if `name`
+/// isn't mangled C++, or if any piece of the signature can't be mapped to a
+/// `Type`, so the caller can fall back to importing a bare name.
+pub fn cpp_signature(name: &str) -> Option<Signature> {
+    let sym = Symbol::new(name).ok()?;
+    let full = sym.demangle(&DemangleOptions::new()).ok()?;
+
+    let open = top_level_index(&full, '(')?;
+    let close = matching_paren(&full, open)?;
+
+    // The return type, when present, is everything before the last top-level
+    // space; the function name follows it.
+    let head = &full[..open];
+    let name_len = split_top_level(head, ' ').last().map_or(0, |name| name.len());
+    let return_type = match head.len().checked_sub(name_len + 1) {
+        Some(space) => parse_type(&head[..space])?,
+        // Only template instantiations encode a return type in the mangled
+        // name; ordinary functions, constructors, destructors and conversion
+        // operators demangle with none and are given a void return type.
+        None => Type::void(),
+    };
+
+    let parameters = split_top_level(&full[open + 1..close], ',')
+        .into_iter()
+        .map(str::trim)
+        .filter(|param| !param.is_empty() && *param != "void")
+        .map(|param| Some(FunctionParameter::new(parse_type(param)?, "")))
+        .collect::<Option<Vec<_>>>()?;
+
+    Some(Signature {
+        return_type,
+        parameters,
+    })
+}
+
+/// Maps a C++ type name to a Binary Ninja `Type`. Handles pointers,
+/// references and `const` qualifiers around a primitive, and the built-in
+/// integer/float widths. Anything else (user-defined structs, templates,
+/// function pointers) is left to the DWARF type parser, so this returns
+/// `None` rather than guessing.
+fn parse_type(s: &str) -> Option<Ref<Type>> {
+    let s = s.trim();
+
+    if let Some(inner) = s.strip_suffix('*') {
+        return Some(Type::pointer(&parse_type(inner)?));
+    }
+    if let Some(inner) = s.strip_suffix('&') {
+        return Some(Type::pointer(&parse_type(inner)?));
+    }
+
+    let s = s.strip_prefix("const ").unwrap_or(s);
+    let s = s.strip_suffix(" const").unwrap_or(s);
+
+    Some(match s {
+        "void" => Type::void(),
+        "bool" => Type::bool(),
+        "char" | "signed char" | "unsigned char" => Type::int(1, s != "unsigned char"),
+        "short" | "short int" => Type::int(2, true),
+        "unsigned short" | "unsigned short int" => Type::int(2, false),
+        "int" => Type::int(4, true),
+        "unsigned" | "unsigned int" => Type::int(4, false),
+        "long" | "long int" => Type::int(8, true),
+        "unsigned long" | "unsigned long int" => Type::int(8, false),
+        "long long" | "long long int" => Type::int(8, true),
+        "unsigned long long" | "unsigned long long int" => Type::int(8, false),
+        "wchar_t" => Type::int(4, true),
+        "float" => Type::float(4),
+        "double" => Type::float(8),
+        "long double" => Type::float(16),
+        _ => return None,
+    })
+}
+
+/// Finds `target` at nesting depth 0, i.e. not inside `(...)`, `<...>` or
+/// `[...]`. Demangled signatures nest all three (template args, function
+/// pointers, array dimensions), so a naive `str::find` would stop early.
+fn top_level_index(s: &str, target: char) -> Option<usize> {
+    let mut depth = 0i32;
+    for (i, c) in s.char_indices() {
+        // Check the target before updating depth: `target` may itself be an
+        // opening bracket (e.g. '('), and that first occurrence at depth 0
+        // is exactly the one callers want, not a later one past the bump.
+        if c == target && depth == 0 {
+            return Some(i);
+        }
+        match c {
+            '(' | '<' | '[' => depth += 1,
+            ')' | '>' | ']' => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+fn matching_paren(s: &str, open: usize) -> Option<usize> {
+    let mut depth = 0i32;
+    for (i, c) in s.char_indices().skip(open) {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn split_top_level(s: &str, sep: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut depth = 0i32;
+    for (i, c) in s.char_indices() {
+        match c {
+            '(' | '<' | '[' => depth += 1,
+            ')' | '>' | ']' => depth -= 1,
+            c if c == sep && depth == 0 => {
+                parts.push(&s[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&s[start..]);
+    parts
+}